@@ -1,17 +1,18 @@
-use crate::data::model::{Node, Relation, TraversalNode, DIR, REL};
+use crate::data::model::{Node, Relation, TraversalNode, REL};
 use crate::db::model::{DbNode, DbNodeSimple};
-use crate::db::scylladb::ScyllaDbService;
-use actix_web::error::ErrorInternalServerError;
+use crate::db::scylladb::{PoolStatus, ScyllaDbService};
+use crate::error::AppError;
+use crate::ingest::{spawn_ingestion, IngestionStore};
 use actix_web::web::Json;
-use actix_web::{get, post, web, web::Data, Error, HttpResponse};
-use color_eyre::Result;
-use futures::future::{BoxFuture, FutureExt};
+use actix_web::{delete, get, patch, post, web, web::Data, HttpResponse};
+use aws_sdk_s3::Client as S3Client;
+use futures::future::join_all;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::Semaphore;
-use tokio::task::JoinHandle;
+use tokio::sync::{Mutex, Semaphore};
 use tracing::info;
 use uuid::Uuid;
 
@@ -27,11 +28,6 @@ pub struct File {
     pub files: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct AppError {
-    message: String,
-}
-
 #[derive(Debug, Serialize, Clone, Deserialize, Default)]
 pub struct GetNodeRequest {
     pub get_tags: Option<bool>,
@@ -54,6 +50,14 @@ pub struct PostSuccessorRequest {
     pub job_id: String,
 }
 
+#[derive(Debug, Serialize, Clone, Deserialize, Default)]
+pub struct PatchNodeRequest {
+    pub name: Option<String>,
+    pub url: Option<String>,
+    pub node_type: Option<String>,
+    pub tags: Option<Vec<(String, String)>>,
+}
+
 #[derive(Debug, Serialize, Clone, Deserialize, Default)]
 pub struct TraversalNodeRequest {
     pub direction: String,
@@ -63,8 +67,18 @@ pub struct TraversalNodeRequest {
 
 pub struct AppState {
     pub db_svc: ScyllaDbService,
+    /// Bounds fan-out work (ingestion workers, traversal expansion) — DB
+    /// connection concurrency is now bounded by `ScyllaDbService`'s own pool.
     pub semaphore: Arc<Semaphore>,
     pub region: String,
+    pub s3: S3Client,
+    pub s3_bucket: String,
+    pub ingestions: IngestionStore,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestionAccepted {
+    pub ingestion_id: String,
 }
 
 #[get("/node/{id}")]
@@ -72,7 +86,7 @@ async fn get_by_id(
     path: web::Path<String>,
     query_data: web::Query<GetNodeRequest>,
     state: Data<AppState>,
-) -> Result<HttpResponse, Error> {
+) -> Result<HttpResponse, AppError> {
     let now = Instant::now();
     let id = path.into_inner();
     info!("get_by_id {}, relations? {:?}", id, query_data);
@@ -92,65 +106,129 @@ async fn traversal_by_id(
     path: web::Path<String>,
     query_data: web::Query<TraversalNodeRequest>,
     state: Data<AppState>,
-) -> Result<HttpResponse, Error> {
+) -> Result<HttpResponse, AppError> {
     let now = Instant::now();
     let id = path.into_inner();
     info!("traversal_by_id: {}", id);
+    Uuid::parse_str(&id).map_err(|_| AppError::InvalidUuid(id.clone()))?;
 
-    let result: Option<TraversalNode> = traversal_recur(
-        state,
-        id,
-        Arc::new(query_data.direction.clone()),
-        Arc::new(query_data.relation_type.clone()),
-        0,
-        query_data.max_depth,
-    )
-    .await;
+    let direction = Arc::new(query_data.direction.clone());
+    let relation_type = Arc::new(query_data.relation_type.clone());
+    let result = traverse(&state, id, direction, relation_type, query_data.max_depth).await;
 
     let elapsed = now.elapsed();
     info!("traversal time: {:.2?}", elapsed);
     Ok(HttpResponse::Ok().json(result))
 }
 
-fn traversal_recur<'a>(
-    state: Data<AppState>,
+/// Breadth-first graph traversal bounded on two axes: `visited` guarantees
+/// every node is fetched at most once (so a cycle like A->B->A terminates
+/// instead of fanning out forever), and `state.semaphore` caps the number of
+/// `get_node_traversal` calls in flight at any one time, across the whole
+/// level rather than per-parent.
+async fn traverse(
+    state: &Data<AppState>,
+    root_id: String,
+    direction: Arc<String>,
+    relation_type: Arc<Option<String>>,
+    max_depth: usize,
+) -> Option<TraversalNode> {
+    let root_uuid = Uuid::parse_str(&root_id).ok()?;
+    let visited = Arc::new(Mutex::new(HashSet::from([root_uuid])));
+
+    let mut nodes: HashMap<Uuid, TraversalNode> = HashMap::new();
+    let mut children: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+
+    let mut frontier = vec![root_id];
+    let mut depth = 0;
+
+    while !frontier.is_empty() {
+        let expand = depth < max_depth;
+        let fetched = join_all(frontier.iter().map(|id| {
+            fetch_and_expand(
+                state,
+                id.clone(),
+                direction.clone(),
+                relation_type.clone(),
+                depth,
+                expand,
+                visited.clone(),
+            )
+        }))
+        .await;
+
+        let mut next_frontier = Vec::new();
+        for (node, fresh_children) in fetched.into_iter().flatten() {
+            let uuid = node.uuid;
+            if !fresh_children.is_empty() {
+                next_frontier.extend(fresh_children.iter().map(Uuid::to_string));
+                children.insert(uuid, fresh_children);
+            }
+            nodes.insert(uuid, node);
+        }
+
+        frontier = next_frontier;
+        depth += 1;
+    }
+
+    build_tree(root_uuid, &nodes, &children)
+}
+
+/// Fetches a single node's relations, gated by `state.semaphore`, and
+/// check-and-inserts each relation id into `visited` so only ids no sibling
+/// fetch has already claimed are returned for expansion.
+async fn fetch_and_expand(
+    state: &Data<AppState>,
     id: String,
     direction: Arc<String>,
     relation_type: Arc<Option<String>>,
     depth: usize,
-    max: usize,
-) -> BoxFuture<'a, Option<TraversalNode>> {
-    async move {
-        let db_nodes = state
-            .db_svc
-            .get_node_traversal(&id, &direction, &relation_type)
-            .await
-            .ok()?;
-        let mut node = TraversalNode::from(db_nodes, depth)?;
-
-        if depth < max && node.relation_ids.len() > 0 {
-            let mut handlers: Vec<JoinHandle<_>> = Vec::new();
-
-            for id in &node.relation_ids {
-                handlers.push(tokio::spawn(traversal_recur(
-                    state.clone(),
-                    id.to_string(),
-                    direction.clone(),
-                    relation_type.clone(),
-                    depth + 1,
-                    max,
-                )));
+    expand: bool,
+    visited: Arc<Mutex<HashSet<Uuid>>>,
+) -> Option<(TraversalNode, Vec<Uuid>)> {
+    let permit = state.semaphore.acquire().await.ok()?;
+    let db_nodes = state
+        .db_svc
+        .get_node_traversal(&id, &direction, &relation_type)
+        .await
+        .ok()?;
+    drop(permit);
+
+    let node = TraversalNode::from(db_nodes, depth)?;
+
+    let mut fresh_children = Vec::new();
+    if expand {
+        let mut seen = visited.lock().await;
+        for rel_id in &node.relation_ids {
+            if let Ok(rel_uuid) = Uuid::parse_str(rel_id) {
+                if seen.insert(rel_uuid) {
+                    fresh_children.push(rel_uuid);
+                }
             }
+        }
+    }
 
-            for thread in handlers {
-                let child = thread.await.ok()?;
-                node.relations.push(child?);
+    Some((node, fresh_children))
+}
+
+/// Reassembles the level-by-level traversal results into the `TraversalNode`
+/// tree the API returns, depth-first from `id` down through `children`.
+fn build_tree(
+    id: Uuid,
+    nodes: &HashMap<Uuid, TraversalNode>,
+    children: &HashMap<Uuid, Vec<Uuid>>,
+) -> Option<TraversalNode> {
+    let mut node = nodes.get(&id)?.clone();
+
+    if let Some(child_ids) = children.get(&id) {
+        for child_id in child_ids {
+            if let Some(child) = build_tree(*child_id, nodes, children) {
+                node.relations.push(child);
             }
         }
-
-        Some(node)
     }
-    .boxed()
+
+    Some(node)
 }
 
 async fn get_node(
@@ -158,13 +236,11 @@ async fn get_node(
     id: &str,
     tags: bool,
     relations: bool,
-) -> Result<Json<Option<Node>>, Error> {
-    let db_nodes = db
-        .get_node(id, tags, relations)
-        .await
-        .map_err(ErrorInternalServerError)?;
+) -> Result<Json<Node>, AppError> {
+    Uuid::parse_str(id).map_err(|_| AppError::InvalidUuid(id.to_owned()))?;
+    let db_nodes = db.get_node(id, tags, relations).await?;
 
-    let node = Node::from(db_nodes);
+    let node = Node::from(db_nodes).ok_or_else(|| AppError::NotFound(format!("node {}", id)))?;
 
     Ok(web::Json(node))
 }
@@ -173,7 +249,7 @@ async fn get_node(
 async fn create_node(
     payload: web::Json<PostNodeRequest>,
     state: Data<AppState>,
-) -> Result<HttpResponse, Error> {
+) -> Result<HttpResponse, AppError> {
     let now = Instant::now();
     info!("create_node: {:?}", payload);
 
@@ -196,14 +272,11 @@ async fn create_node(
         vec![],
     );
 
-    let result = state.db_svc.save_nodes(vec![db_node]).await;
+    state.db_svc.save_nodes(vec![db_node]).await?;
 
     let elapsed = now.elapsed();
     info!("create_node time: {:.2?}", elapsed);
-    match result {
-        Ok(_) => Ok(HttpResponse::Ok().json(node)),
-        Err(e) => Err(ErrorInternalServerError(e)),
-    }
+    Ok(HttpResponse::Ok().json(node))
 }
 
 #[post("/node/{id}/successor")]
@@ -211,23 +284,21 @@ async fn add_successor(
     path: web::Path<String>,
     payload: web::Json<PostSuccessorRequest>,
     state: Data<AppState>,
-) -> Result<HttpResponse, Error> {
+) -> Result<HttpResponse, AppError> {
     let now = Instant::now();
     let id: String = path.into_inner();
     info!("add_successor {}", id);
 
-    // UUID from String
-    let uuid = Uuid::parse_str(&id).unwrap();
+    let uuid = Uuid::parse_str(&id).map_err(|_| AppError::InvalidUuid(id.clone()))?;
+    let target_uuid =
+        Uuid::parse_str(&payload.uuid).map_err(|_| AppError::InvalidUuid(payload.uuid.clone()))?;
 
-    // create the new edge from the payload data
-    let edge: DbNode = DbNode::relation(
-        uuid,
-        payload.job_id.clone(),
-        DIR::OUT.to_string(),
-        REL::ISPARENT.to_string(),
-        payload.uuid.clone(),
-        payload.name.clone(),
-    );
+    let source = state.db_svc.get_node(&id, false, false).await?;
+    let source_name = source
+        .into_iter()
+        .next()
+        .map(|n| n.name)
+        .ok_or_else(|| AppError::NotFound(format!("node {}", id)))?;
 
     let relation = Relation::from(
         payload.name.clone(),
@@ -235,13 +306,117 @@ async fn add_successor(
         payload.uuid.clone(),
         true,
     );
+    // forward edge, in this node's partition, pointing at the successor
+    let forward = DbNode::from_rel(uuid, payload.job_id.clone(), &relation);
 
-    let result = state.db_svc.save_nodes(vec![edge]).await;
+    // mirrored edge, in the successor's partition, pointing back at this
+    // node, so `delete_node` can find and remove both sides of the relation
+    let mirror = Relation::from(source_name, REL::ISCHILD.to_string(), id.clone(), false);
+    let backward = DbNode::from_rel(target_uuid, payload.job_id.clone(), &mirror);
+
+    state.db_svc.save_nodes(vec![forward, backward]).await?;
 
     let elapsed = now.elapsed();
     info!("add_successor time: {:.2?}", elapsed);
-    match result {
-        Ok(_) => Ok(HttpResponse::Ok().json(relation)),
-        Err(e) => Err(ErrorInternalServerError(e)),
+    Ok(HttpResponse::Ok().json(relation))
+}
+
+#[patch("/node/{id}")]
+async fn update_node(
+    path: web::Path<String>,
+    payload: web::Json<PatchNodeRequest>,
+    state: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    info!("update_node {}", id);
+    Uuid::parse_str(&id).map_err(|_| AppError::InvalidUuid(id.clone()))?;
+
+    let updated = state
+        .db_svc
+        .update_node(
+            &id,
+            payload.name.clone(),
+            payload.url.clone(),
+            payload.node_type.clone(),
+            payload.tags.clone(),
+        )
+        .await?;
+
+    if !updated {
+        return Err(AppError::NotFound(format!("node {}", id)));
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/node/{id}")]
+async fn delete_node(
+    path: web::Path<String>,
+    state: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let id = path.into_inner();
+    info!("delete_node {}", id);
+    Uuid::parse_str(&id).map_err(|_| AppError::InvalidUuid(id.clone()))?;
+
+    state.db_svc.delete_node(&id).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/node/{id}/successor/{target}")]
+async fn delete_successor(
+    path: web::Path<(String, String)>,
+    state: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let (id, target) = path.into_inner();
+    info!("delete_successor {} -> {}", id, target);
+    Uuid::parse_str(&id).map_err(|_| AppError::InvalidUuid(id.clone()))?;
+    Uuid::parse_str(&target).map_err(|_| AppError::InvalidUuid(target.clone()))?;
+
+    state.db_svc.delete_relation(&id, &target).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/ingest")]
+async fn start_ingestion(
+    payload: web::Json<IngestionRequest>,
+    state: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let ingestion_id = payload.ingestion_id.clone();
+    info!(
+        "start_ingestion {}, {} file(s)",
+        ingestion_id,
+        payload.files.len()
+    );
+
+    spawn_ingestion(state, ingestion_id.clone(), payload.files.clone()).await;
+
+    Ok(HttpResponse::Ok().json(IngestionAccepted { ingestion_id }))
+}
+
+#[get("/ingest/{ingestion_id}")]
+async fn get_ingestion_status(
+    path: web::Path<String>,
+    state: Data<AppState>,
+) -> Result<HttpResponse, AppError> {
+    let ingestion_id = path.into_inner();
+    let statuses = state.ingestions.lock().await;
+
+    match statuses.get(&ingestion_id) {
+        Some(status) => Ok(HttpResponse::Ok().json(status)),
+        None => Err(AppError::NotFound(format!("ingestion {}", ingestion_id))),
     }
 }
+
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    pub db_pool: PoolStatus,
+}
+
+#[get("/health")]
+async fn health(state: Data<AppState>) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(HealthResponse {
+        db_pool: state.db_svc.pool_status(),
+    }))
+}
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub host: String,
+    pub port: u16,
+    pub region: String,
+    pub parallel_files: usize,
+    pub db_parallelism: usize,
+    pub db_dc: String,
+    pub db_url: String,
+    pub migrations_dir: String,
+    pub s3_bucket: String,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, envy::Error> {
+        envy::from_env::<Config>()
+    }
+}
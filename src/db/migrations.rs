@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::fs;
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use scylla::Session;
+use tracing::info;
+
+const ENSURE_MIGRATIONS_TABLE: &str = "CREATE TABLE IF NOT EXISTS schema_migrations \
+    (version int PRIMARY KEY, name text, applied_at timestamp)";
+
+const SELECT_APPLIED: &str = "SELECT version FROM schema_migrations";
+
+const INSERT_APPLIED: &str =
+    "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?, ?, toTimestamp(now()))";
+
+struct Migration {
+    version: i32,
+    name: String,
+    cql: String,
+}
+
+/// Applies every `<version>_<name>.cql` file in `migrations_dir` not yet
+/// recorded in `schema_migrations`, in ascending version order. Scylla has
+/// no multi-statement transactions, so each statement within a file is
+/// applied one at a time and the tracking row is only inserted once the
+/// whole file succeeds; a failure stops the run, leaving the table accurate
+/// so a re-run resumes at the first unapplied version.
+pub async fn run(session: &Session, migrations_dir: &str) -> Result<()> {
+    session.query(ENSURE_MIGRATIONS_TABLE, &[]).await?;
+
+    let applied: HashSet<i32> = session
+        .query(SELECT_APPLIED, &[])
+        .await?
+        .rows_typed::<(i32,)>()?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|(version,)| version)
+        .collect();
+
+    let mut migrations = read_migrations(migrations_dir)?;
+    migrations.sort_by_key(|m| m.version);
+
+    for migration in migrations {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+
+        info!(
+            "applying migration {:04}_{}",
+            migration.version, migration.name
+        );
+
+        for statement in migration.cql.split(';') {
+            let statement = statement.trim();
+            if statement.is_empty() {
+                continue;
+            }
+            session.query(statement.to_owned(), &[]).await?;
+        }
+
+        session
+            .query(INSERT_APPLIED, (migration.version, migration.name.clone()))
+            .await?;
+    }
+
+    Ok(())
+}
+
+fn read_migrations(migrations_dir: &str) -> Result<Vec<Migration>> {
+    let mut migrations = Vec::new();
+
+    for entry in fs::read_dir(migrations_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("cql") {
+            continue;
+        }
+
+        let file_stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| eyre!("invalid migration file name: {}", path.display()))?;
+
+        let (version_str, name) = file_stem.split_once('_').ok_or_else(|| {
+            eyre!(
+                "migration file must be named '<version>_<name>.cql': {}",
+                path.display()
+            )
+        })?;
+
+        let version: i32 = version_str.parse().map_err(|_| {
+            eyre!(
+                "migration file must start with a numeric version: {}",
+                path.display()
+            )
+        })?;
+
+        migrations.push(Migration {
+            version,
+            name: name.to_owned(),
+            cql: fs::read_to_string(&path)?,
+        });
+    }
+
+    Ok(migrations)
+}
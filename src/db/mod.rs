@@ -0,0 +1,3 @@
+mod migrations;
+pub mod model;
+pub mod scylladb;
@@ -36,6 +36,11 @@ pub struct DbRelation {
 }
 
 impl DbNode {
+    /// Clustering-key value identifying a node's own row within its
+    /// partition, as opposed to one of its relation rows (keyed by the
+    /// related node's uuid).
+    pub const BASE_ROW: &'static str = "";
+
     pub fn relation(
         uuid: Uuid,
         job_id: String,
@@ -61,7 +66,10 @@ impl DbNode {
             uuid: node.uuid,
             direction: None,
             relation: None,
-            relates_to: None,
+            // `relates_to` is the second component of the `nodes` table's
+            // primary key, after `uuid`, so it can't be NULL; the node's own row uses
+            // "" rather than a relation's target uuid.
+            relates_to: Some(Self::BASE_ROW.to_owned()),
             name: node.name.to_owned(),
             job_id: node.job_id.to_owned(),
             url: node.url.to_owned(),
@@ -0,0 +1,263 @@
+use async_trait::async_trait;
+use color_eyre::Result;
+use deadpool::managed::{Manager, Metrics, Object, Pool, PoolConfig, RecycleResult};
+use scylla::{Session, SessionBuilder};
+use tracing::info;
+
+use crate::db::migrations;
+use crate::db::model::{DbNode, DbRelation};
+
+const INSERT_NODE: &str = "INSERT INTO nodes \
+    (uuid, direction, relation, relates_to, name, job_id, url, node_type, tags) \
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)";
+
+const SELECT_NODE: &str = "SELECT uuid, direction, relation, relates_to, name, job_id, url, node_type, tags \
+    FROM nodes WHERE uuid = ?";
+
+const SELECT_BASE_ROW: &str = "SELECT uuid, direction, relation, relates_to, name, job_id, url, node_type, tags \
+    FROM nodes WHERE uuid = ? AND relates_to = ?";
+
+const UPDATE_NODE: &str =
+    "UPDATE nodes SET name = ?, url = ?, node_type = ?, tags = ? WHERE uuid = ? AND relates_to = ?";
+
+const DELETE_PARTITION: &str = "DELETE FROM nodes WHERE uuid = ?";
+
+const DELETE_RELATION: &str = "DELETE FROM nodes WHERE uuid = ? AND relates_to = ?";
+
+const SELECT_TRAVERSAL: &str = "SELECT uuid, direction, relation, relates_to, name, node_type \
+    FROM nodes WHERE uuid = ?";
+
+/// `deadpool` manager that opens a fresh `scylla::Session` per pooled
+/// connection and drops any session that has lost its cluster connectivity
+/// instead of handing it back out.
+struct ScyllaManager {
+    db_url: String,
+}
+
+#[async_trait]
+impl Manager for ScyllaManager {
+    type Type = Session;
+    type Error = color_eyre::eyre::Error;
+
+    async fn create(&self) -> Result<Session> {
+        SessionBuilder::new()
+            .known_node(&self.db_url)
+            .build()
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn recycle(&self, session: &mut Session, _: &Metrics) -> RecycleResult<Self::Error> {
+        session
+            .query("SELECT host_id FROM system.local WHERE key = 'local'", &[])
+            .await
+            .map(|_| ())
+            .map_err(|e| e.into())
+    }
+}
+
+/// In-use/idle snapshot of the underlying connection pool, returned by
+/// `GET /health`.
+#[derive(Debug, serde::Serialize)]
+pub struct PoolStatus {
+    pub size: usize,
+    pub available: usize,
+    pub in_use: usize,
+}
+
+/// Owns a pool of ScyllaDB sessions (sized by `db_parallelism`) instead of a
+/// single shared session. Each query checks out a connection and returns it
+/// to the pool on drop, providing backpressure under heavy fan-out and
+/// recycling connections that fail the manager's liveness check.
+pub struct ScyllaDbService {
+    pool: Pool<ScyllaManager>,
+}
+
+impl ScyllaDbService {
+    pub async fn new(db_dc: String, db_url: String, db_parallelism: usize) -> Self {
+        let manager = ScyllaManager {
+            db_url: db_url.clone(),
+        };
+        let pool = Pool::builder(manager)
+            .config(PoolConfig::new(db_parallelism))
+            .build()
+            .expect("Failed to build ScyllaDB connection pool");
+
+        info!(
+            "Created ScyllaDB connection pool for {} (dc {}), pool size {}",
+            db_url, db_dc, db_parallelism
+        );
+
+        Self { pool }
+    }
+
+    async fn conn(&self) -> Result<Object<ScyllaManager>> {
+        self.pool.get().await.map_err(Into::into)
+    }
+
+    pub fn pool_status(&self) -> PoolStatus {
+        let status = self.pool.status();
+        PoolStatus {
+            size: status.size,
+            available: status.available.max(0) as usize,
+            in_use: status.size.saturating_sub(status.available.max(0) as usize),
+        }
+    }
+
+    /// Applies pending files from `migrations_dir` in ascending version
+    /// order, recording each in the `schema_migrations` table as it succeeds.
+    pub async fn run_migrations(&self, migrations_dir: &str) -> Result<()> {
+        let conn = self.conn().await?;
+        migrations::run(&conn, migrations_dir).await
+    }
+
+    pub async fn get_node(&self, id: &str, tags: bool, relations: bool) -> Result<Vec<DbNode>> {
+        let uuid = uuid::Uuid::parse_str(id)?;
+        let conn = self.conn().await?;
+        let mut rows = conn
+            .query(SELECT_NODE, (uuid,))
+            .await?
+            .rows_typed::<DbNode>()?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !tags {
+            for row in &mut rows {
+                row.tags = None;
+            }
+        }
+        if !relations {
+            rows.truncate(1);
+        }
+
+        Ok(rows)
+    }
+
+    pub async fn get_node_traversal(
+        &self,
+        id: &str,
+        _direction: &str,
+        _relation_type: &Option<String>,
+    ) -> Result<Vec<DbRelation>> {
+        let uuid = uuid::Uuid::parse_str(id)?;
+        let conn = self.conn().await?;
+        let rows = conn
+            .query(SELECT_TRAVERSAL, (uuid,))
+            .await?
+            .rows_typed::<DbRelation>()?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows)
+    }
+
+    pub async fn save_nodes(&self, nodes: Vec<DbNode>) -> Result<()> {
+        let conn = self.conn().await?;
+        for node in nodes {
+            conn.query(
+                INSERT_NODE,
+                (
+                    node.uuid,
+                    node.direction,
+                    node.relation,
+                    node.relates_to,
+                    node.name,
+                    node.job_id,
+                    node.url,
+                    node.node_type,
+                    node.tags,
+                ),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Merges the provided fields onto the node's base row, leaving omitted
+    /// fields untouched. Returns `false` without writing anything if the node
+    /// doesn't exist, so callers can distinguish "not found" from other
+    /// failures instead of collapsing both into a generic database error.
+    pub async fn update_node(
+        &self,
+        id: &str,
+        name: Option<String>,
+        url: Option<String>,
+        node_type: Option<String>,
+        tags: Option<Vec<(String, String)>>,
+    ) -> Result<bool> {
+        let uuid = uuid::Uuid::parse_str(id)?;
+        let conn = self.conn().await?;
+
+        let existing = match conn
+            .query(SELECT_BASE_ROW, (uuid, DbNode::BASE_ROW))
+            .await?
+            .rows_typed::<DbNode>()?
+            .next()
+        {
+            Some(row) => row?,
+            None => return Ok(false),
+        };
+
+        conn.query(
+            UPDATE_NODE,
+            (
+                name.unwrap_or(existing.name),
+                url.unwrap_or(existing.url),
+                node_type.unwrap_or(existing.node_type),
+                tags.or(existing.tags),
+                uuid,
+                DbNode::BASE_ROW,
+            ),
+        )
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Removes a node's entire partition (its base row and every relation
+    /// row stored under it), first deleting the mirrored relation row from
+    /// each related node's partition so no dangling relation is left
+    /// pointing at the deleted node. Relies on relations being written to
+    /// both partitions at creation time (see `add_successor`/ingestion).
+    pub async fn delete_node(&self, id: &str) -> Result<()> {
+        let uuid = uuid::Uuid::parse_str(id)?;
+        let conn = self.conn().await?;
+
+        let rows = conn
+            .query(SELECT_NODE, (uuid,))
+            .await?
+            .rows_typed::<DbNode>()?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for row in rows {
+            let Some(relates_to) = row.relates_to else {
+                continue;
+            };
+            if relates_to == DbNode::BASE_ROW {
+                continue;
+            }
+
+            let other = uuid::Uuid::parse_str(&relates_to)?;
+            conn.query(DELETE_RELATION, (other, id.to_owned()))
+                .await?;
+        }
+
+        conn.query(DELETE_PARTITION, (uuid,)).await?;
+
+        Ok(())
+    }
+
+    /// Removes a single relation between two nodes, on both sides of the
+    /// bidirectional representation.
+    pub async fn delete_relation(&self, id: &str, target: &str) -> Result<()> {
+        let uuid = uuid::Uuid::parse_str(id)?;
+        let target_uuid = uuid::Uuid::parse_str(target)?;
+        let conn = self.conn().await?;
+
+        conn.query(DELETE_RELATION, (uuid, target.to_owned()))
+            .await?;
+        conn.query(DELETE_RELATION, (target_uuid, id.to_owned()))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+use thiserror::Error;
+
+/// Crate-wide error type. Every variant maps to a specific HTTP status via
+/// [`ResponseError`] instead of collapsing into a 500, and serializes as
+/// `{"message": ...}` so clients get a body to work with.
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("not found: {0}")]
+    NotFound(String),
+
+    #[error("invalid uuid: {0}")]
+    InvalidUuid(String),
+
+    #[error("database error: {0}")]
+    DbError(String),
+}
+
+impl From<color_eyre::eyre::Error> for AppError {
+    fn from(e: color_eyre::eyre::Error) -> Self {
+        AppError::DbError(e.to_string())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    message: String,
+}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::InvalidUuid(_) => StatusCode::BAD_REQUEST,
+            AppError::DbError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(ErrorBody {
+            message: self.to_string(),
+        })
+    }
+}
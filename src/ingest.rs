@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use actix_web::web::Data;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::AppState;
+use crate::data::model::{Relation, DIR};
+use crate::db::model::DbNode;
+
+/// Progress counters for a single `POST /ingest` request, keyed by `ingestion_id`.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct IngestionStatus {
+    pub pending: usize,
+    pub done: usize,
+    pub failed: usize,
+}
+
+impl IngestionStatus {
+    fn new(total: usize) -> Self {
+        Self {
+            pending: total,
+            done: 0,
+            failed: 0,
+        }
+    }
+}
+
+/// Tracks in-flight and completed ingestions for the lifetime of the process.
+pub type IngestionStore = Arc<Mutex<HashMap<String, IngestionStatus>>>;
+
+#[derive(Debug, Deserialize)]
+struct NodeRecord {
+    uuid: Uuid,
+    name: String,
+    node_type: String,
+    url: String,
+    job_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelationRecord {
+    uuid: Uuid,
+    name: String,
+    job_id: String,
+    direction: String,
+    relation: String,
+    relates_to: Uuid,
+    relates_to_name: String,
+}
+
+impl RelationRecord {
+    /// Validates `direction` against [`DIR`] and returns whether the relation
+    /// is outbound as seen from `uuid`'s side, rejecting anything that isn't
+    /// `IN`/`OUT` instead of silently writing it through to storage.
+    fn outbound(&self) -> Result<bool> {
+        if self.direction == DIR::OUT.to_string() {
+            Ok(true)
+        } else if self.direction == DIR::IN.to_string() {
+            Ok(false)
+        } else {
+            Err(eyre!(
+                "invalid relation direction {:?} for node {}",
+                self.direction,
+                self.uuid
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct IngestRecord {
+    node: Option<NodeRecord>,
+    relation: Option<RelationRecord>,
+}
+
+/// Registers `ingestion_id` in `state.ingestions` and hands each file to the
+/// worker pool, bounded by `state.semaphore`. Returns immediately; callers
+/// poll `GET /ingest/{id}` for progress.
+pub async fn spawn_ingestion(state: Data<AppState>, ingestion_id: String, files: Vec<String>) {
+    state
+        .ingestions
+        .lock()
+        .await
+        .insert(ingestion_id.clone(), IngestionStatus::new(files.len()));
+
+    for file in files {
+        let state = state.clone();
+        let ingestion_id = ingestion_id.clone();
+
+        tokio::spawn(async move {
+            let _permit = state.semaphore.acquire().await;
+            let result = ingest_file(&state, &file).await;
+
+            let mut statuses = state.ingestions.lock().await;
+            if let Some(status) = statuses.get_mut(&ingestion_id) {
+                status.pending = status.pending.saturating_sub(1);
+                match result {
+                    Ok(_) => {
+                        info!("ingestion {} file {} done", ingestion_id, file);
+                        status.done += 1;
+                    }
+                    Err(e) => {
+                        warn!("ingestion {} file {} failed: {}", ingestion_id, file, e);
+                        status.failed += 1;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Downloads a single S3 object, parses it into node/relation records and
+/// bulk-saves the resulting `DbNode`s.
+async fn ingest_file(state: &AppState, key: &str) -> Result<()> {
+    let object = state
+        .s3
+        .get_object()
+        .bucket(&state.s3_bucket)
+        .key(key)
+        .send()
+        .await?;
+    let bytes = object.body.collect().await?.into_bytes();
+    let records: Vec<IngestRecord> = serde_json::from_slice(&bytes)?;
+
+    let mut db_nodes = Vec::with_capacity(records.len());
+    for record in records {
+        if let Some(node) = record.node {
+            db_nodes.push(DbNode::from_simple(crate::db::model::DbNodeSimple {
+                uuid: node.uuid,
+                name: node.name,
+                node_type: node.node_type,
+                url: node.url,
+                job_id: node.job_id,
+            }));
+        }
+        if let Some(rel) = record.relation {
+            let outbound = rel.outbound()?;
+
+            // forward row, in `uuid`'s partition, pointing at `relates_to`
+            let forward = Relation::from(
+                rel.relates_to_name.clone(),
+                rel.relation.clone(),
+                rel.relates_to.to_string(),
+                outbound,
+            );
+            db_nodes.push(DbNode::from_rel(rel.uuid, rel.job_id.clone(), &forward));
+
+            // mirrored row, in `relates_to`'s partition, pointing back at `uuid`,
+            // so a later `delete_node` can find and remove both sides
+            let backward = Relation::from(
+                rel.name.clone(),
+                rel.relation,
+                rel.uuid.to_string(),
+                !outbound,
+            );
+            db_nodes.push(DbNode::from_rel(rel.relates_to, rel.job_id, &backward));
+        }
+    }
+
+    state.db_svc.save_nodes(db_nodes).await
+}
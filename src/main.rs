@@ -2,23 +2,34 @@ mod api;
 mod config;
 mod data;
 mod db;
+mod error;
+mod ingest;
 
 extern crate num_cpus;
 extern crate serde_json;
 
-use crate::api::{create_node, get_by_id, traversal_by_id, AppState};
+use crate::api::{
+    create_node, delete_node, delete_successor, get_by_id, get_ingestion_status, health,
+    start_ingestion, traversal_by_id, update_node, AppState,
+};
 use crate::config::Config;
 use crate::db::scylladb::ScyllaDbService;
 use actix_web::middleware::Logger;
 use actix_web::{web::Data, App, HttpServer};
 use color_eyre::Result;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Semaphore;
+use tokio::sync::{Mutex, Semaphore};
 use tracing::info;
 
+/// Operating mode selected by the first CLI argument: `serve` (default)
+/// starts the HTTP server, `migrate` applies pending migrations and exits.
+const MODE_MIGRATE: &str = "migrate";
+
 #[actix_web::main]
 async fn main() -> Result<()> {
     let config = Config::from_env().expect("Server configuration");
+    let mode = std::env::args().nth(1).unwrap_or_default();
 
     let port = config.port;
     let host = config.host.clone();
@@ -32,19 +43,28 @@ async fn main() -> Result<()> {
         num_cpus, parallel_files, db_parallelism, region
     );
 
-    let db = ScyllaDbService::new(
-        config.db_dc,
-        config.db_url,
-        db_parallelism,
-        config.schema_file,
-    )
-    .await;
+    let db = ScyllaDbService::new(config.db_dc, config.db_url, db_parallelism).await;
+
+    if mode == MODE_MIGRATE {
+        info!("Running pending migrations from {}", config.migrations_dir);
+        db.run_migrations(&config.migrations_dir).await?;
+        return Ok(());
+    }
+
+    let aws_config = aws_config::from_env()
+        .region(aws_sdk_s3::config::Region::new(region.clone()))
+        .load()
+        .await;
+    let s3 = aws_sdk_s3::Client::new(&aws_config);
 
     let sem = Arc::new(Semaphore::new(parallel_files));
     let data = Data::new(AppState {
         db_svc: db,
         semaphore: sem,
         region,
+        s3,
+        s3_bucket: config.s3_bucket,
+        ingestions: Arc::new(Mutex::new(HashMap::new())),
     });
 
     info!("Starting server at http://{}:{}/", host, port);
@@ -55,6 +75,12 @@ async fn main() -> Result<()> {
             .service(get_by_id)
             .service(traversal_by_id)
             .service(create_node)
+            .service(update_node)
+            .service(delete_node)
+            .service(delete_successor)
+            .service(start_ingestion)
+            .service(get_ingestion_status)
+            .service(health)
     })
     .bind(format!("{}:{}", host, port))?
     .workers(num_cpus * 2)